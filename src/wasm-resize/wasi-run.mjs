@@ -0,0 +1,10 @@
+// Cargo runner for the wasm32-wasip1 test binaries (see BUILD.md):
+// node wasi-run.mjs <binary.wasm> [test args...]
+import { readFile } from 'node:fs/promises';
+import { WASI } from 'node:wasi';
+import { argv, env, exit } from 'node:process';
+
+const wasi = new WASI({ version: 'preview1', args: argv.slice(2), env, returnOnExit: true });
+const module = await WebAssembly.compile(await readFile(argv[2]));
+const instance = await WebAssembly.instantiate(module, wasi.getImportObject());
+exit(wasi.start(instance));